@@ -100,11 +100,12 @@ use axum_core::{
     extract::{FromRef, FromRequestParts},
     response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
 };
-use axum_extra::extract::cookie::{Cookie, SignedCookieJar};
+use axum_extra::extract::cookie::{Cookie, PrivateCookieJar, SignedCookieJar};
 use http::{request::Parts, StatusCode};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{borrow::Cow, fmt};
 use std::{
+    cell::Cell,
     convert::{Infallible, TryInto},
     time::Duration,
 };
@@ -114,24 +115,46 @@ pub use axum_extra::extract::cookie::Key;
 /// Extractor for setting outgoing flash messages.
 ///
 /// The flashes will be stored in a signed cookie.
+/// With [`Config::encrypt_cookies`] turned on, the cookie is encrypted instead.
+///
+/// `T` is the type of the payload carried by each flash message and
+/// defaults to `String`, which gives you the `debug`/`info`/`success`/...
+/// convenience methods below. Set `T` to your own `Serialize +
+/// DeserializeOwned` type to flash structured data instead, pushing values
+/// with [`Flash::push_value`].
 #[derive(Clone)]
-pub struct Flash {
-    flashes: Vec<FlashMessage>,
+pub struct Flash<T = String> {
+    flashes: Vec<FlashMessage<T>>,
     use_secure_cookies: bool,
+    encrypt: bool,
     key: Key,
+    cookie_name: Cow<'static, str>,
+    cookie_path: Cow<'static, str>,
+    cookie_max_age: Duration,
+    same_site: cookie::SameSite,
+    max_cookie_size: usize,
 }
 
-impl fmt::Debug for Flash {
+impl<T> fmt::Debug for Flash<T>
+where
+    T: fmt::Debug,
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Flash")
             .field("flashes", &self.flashes)
             .field("use_secure_cookies", &self.use_secure_cookies)
+            .field("encrypt", &self.encrypt)
             .field("key", &"REDACTED")
+            .field("cookie_name", &self.cookie_name)
+            .field("cookie_path", &self.cookie_path)
+            .field("cookie_max_age", &self.cookie_max_age)
+            .field("same_site", &self.same_site)
+            .field("max_cookie_size", &self.max_cookie_size)
             .finish()
     }
 }
 
-impl Flash {
+impl Flash<String> {
     /// Push an `Debug` flash message.
     pub fn debug(self, message: impl Into<String>) -> Self {
         self.push(Level::Debug, message)
@@ -158,20 +181,73 @@ impl Flash {
     }
 
     /// Push a flash message with the given level and message.
-    pub fn push(mut self, level: Level, message: impl Into<String>) -> Self {
+    pub fn push(self, level: Level, message: impl Into<String>) -> Self {
+        self.push_value(level, message.into())
+    }
+}
+
+impl<T> Flash<T> {
+    /// Push a flash message carrying an arbitrary value of type `T`.
+    ///
+    /// This is the generic escape hatch behind `debug`/`info`/... for when
+    /// `T` isn't `String`.
+    pub fn push_value(mut self, level: Level, value: T) -> Self {
         self.flashes.push(FlashMessage {
-            message: message.into(),
             level,
+            message: value,
         });
         self
     }
+
+    /// Get the values of the flash messages pushed so far, without their
+    /// levels.
+    pub fn messages(&self) -> impl Iterator<Item = &T> + '_ {
+        self.flashes.iter().map(|message| &message.message)
+    }
+
+    /// Returns `true` if, were this value converted into a response right
+    /// now, the oldest messages would have to be evicted to keep the
+    /// cookie under [`Config::max_cookie_size`].
+    ///
+    /// Browsers cap cookies around 4 KB, and a dropped cookie loses every
+    /// message in it, not just the newest one. This lets a handler check
+    /// before returning whether that's about to happen.
+    pub fn would_truncate(&self) -> bool
+    where
+        T: Serialize,
+    {
+        let json =
+            serde_json::to_string(&self.flashes).expect("failed to serialize flash messages");
+        encoded_cookie_value_len(json.len(), self.encrypt) > self.max_cookie_size
+    }
+}
+
+/// Estimate the size in bytes of the cookie value axum-extra will actually
+/// send once it signs (or encrypts) `json_len` bytes of serialized
+/// flashes.
+///
+/// A `SignedCookieJar` value is the base64-encoded 32 byte HMAC-SHA256 tag
+/// (44 characters) followed by the JSON itself, unencoded. A
+/// `PrivateCookieJar` value is the base64 encoding of a 12 byte nonce, the
+/// ciphertext (as long as the JSON) and a 16 byte AEAD tag. Comparing the
+/// raw JSON length against `max_cookie_size` would let a cookie that's
+/// fine pre-encoding blow past the browser's ~4 KB cap post-encoding.
+fn encoded_cookie_value_len(json_len: usize, encrypt: bool) -> usize {
+    if encrypt {
+        let raw_len = json_len + 12 + 16;
+        // base64 encodes every 3 raw bytes as 4 characters, rounding up.
+        (raw_len + 2) / 3 * 4
+    } else {
+        json_len + 44
+    }
 }
 
 #[async_trait]
-impl<S> FromRequestParts<S> for Flash
+impl<S, T> FromRequestParts<S> for Flash<T>
 where
     S: Send + Sync,
     Config: FromRef<S>,
+    T: Send,
 {
     type Rejection = Infallible;
 
@@ -181,45 +257,84 @@ where
         Ok(Self {
             key: config.key,
             use_secure_cookies: config.use_secure_cookies,
+            encrypt: config.encrypt_cookies,
             flashes: Default::default(),
+            cookie_name: config.cookie_name,
+            cookie_path: config.cookie_path,
+            cookie_max_age: config.cookie_max_age,
+            same_site: config.same_site,
+            max_cookie_size: config.max_cookie_size,
         })
     }
 }
 
-const COOKIE_NAME: &str = "axum-flash";
+const DEFAULT_COOKIE_NAME: &str = "axum-flash";
+const DEFAULT_MAX_COOKIE_SIZE: usize = 3900;
 
-impl IntoResponseParts for Flash {
+impl<T> IntoResponseParts for Flash<T>
+where
+    T: Serialize,
+{
     type Error = Infallible;
 
     fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
-        let json =
-            serde_json::to_string(&self.flashes).expect("failed to serialize flash messages");
-
-        let cookies = SignedCookieJar::new(self.key.clone());
-
-        let cookies = cookies.add(create_cookie(json, self.use_secure_cookies));
-        cookies.into_response_parts(res)
+        let mut flashes = self.flashes;
+
+        // Browsers cap cookies around 4 KB. If the serialized flashes don't
+        // fit once signed (or encrypted), evict the oldest ones first so
+        // the most recent messages are the ones that survive, rather than
+        // silently losing the whole cookie to the browser's size limit.
+        let json = loop {
+            let json =
+                serde_json::to_string(&flashes).expect("failed to serialize flash messages");
+            if encoded_cookie_value_len(json.len(), self.encrypt) <= self.max_cookie_size
+                || flashes.is_empty()
+            {
+                break json;
+            }
+            flashes.remove(0);
+        };
+
+        let cookie = create_cookie(
+            self.cookie_name,
+            json,
+            self.cookie_path,
+            self.cookie_max_age,
+            self.same_site,
+            self.use_secure_cookies,
+        );
+
+        if self.encrypt {
+            let cookies = PrivateCookieJar::new(self.key).add(cookie);
+            cookies.into_response_parts(res)
+        } else {
+            let cookies = SignedCookieJar::new(self.key).add(cookie);
+            cookies.into_response_parts(res)
+        }
     }
 }
 
 pub(crate) fn create_cookie(
+    name: Cow<'static, str>,
     value: impl Into<Cow<'static, str>>,
+    path: Cow<'static, str>,
+    max_age: Duration,
+    same_site: cookie::SameSite,
     use_secure_cookies: bool,
 ) -> Cookie<'static> {
     // process is inspired by
     // https://github.com/LukeMathWalker/actix-web-flash-messages/blob/main/src/storage/cookies.rs#L54
-    Cookie::build((COOKIE_NAME, value))
+    Cookie::build((name, value))
         // only send the cookie for https (maybe)
         .secure(use_secure_cookies)
         // don't allow javascript to access the cookie
         .http_only(true)
         // don't send the cookie to other domains
-        .same_site(cookie::SameSite::Strict)
-        // allow the cookie for all paths
-        .path("/")
-        // expire after 10 minutes
+        .same_site(same_site)
+        // allow the cookie for the given path
+        .path(path)
         .max_age(
-            Duration::from_secs(10 * 60)
+            max_age
                 .try_into()
                 .expect("failed to convert `std::time::Duration` to `time::Duration`"),
         )
@@ -227,11 +342,11 @@ pub(crate) fn create_cookie(
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct FlashMessage {
+struct FlashMessage<T = String> {
     #[serde(rename = "l")]
     level: Level,
     #[serde(rename = "m")]
-    message: String,
+    message: T,
 }
 
 /// Verbosity level of a flash message.
@@ -253,7 +368,13 @@ pub enum Level {
 #[derive(Clone)]
 pub struct Config {
     use_secure_cookies: bool,
+    encrypt_cookies: bool,
     key: Key,
+    cookie_name: Cow<'static, str>,
+    cookie_path: Cow<'static, str>,
+    cookie_max_age: Duration,
+    same_site: cookie::SameSite,
+    max_cookie_size: usize,
 }
 
 impl Config {
@@ -263,7 +384,13 @@ impl Config {
     pub fn new(key: Key) -> Self {
         Self {
             use_secure_cookies: true,
+            encrypt_cookies: false,
             key,
+            cookie_name: Cow::Borrowed(DEFAULT_COOKIE_NAME),
+            cookie_path: Cow::Borrowed("/"),
+            cookie_max_age: Duration::from_secs(10 * 60),
+            same_site: cookie::SameSite::Strict,
+            max_cookie_size: DEFAULT_MAX_COOKIE_SIZE,
         }
     }
 
@@ -281,13 +408,84 @@ impl Config {
         self.use_secure_cookies = use_secure_cookies;
         self
     }
+
+    /// Encrypt the flash cookie instead of only signing it.
+    ///
+    /// A signed cookie (the default) is tamper-proof but still sent to the
+    /// browser in plaintext, so anyone with access to the cookie can read
+    /// the flash messages. Turning this on switches to an encrypted
+    /// (AEAD) cookie, using the same `Key`, so the message contents aren't
+    /// observable either.
+    ///
+    /// Defaults to `false` for backward compatibility.
+    pub fn encrypt_cookies(mut self, encrypt_cookies: bool) -> Self {
+        self.encrypt_cookies = encrypt_cookies;
+        self
+    }
+
+    /// Set the name of the cookie used to store the flash messages.
+    ///
+    /// Defaults to `"axum-flash"`.
+    pub fn cookie_name(mut self, cookie_name: impl Into<Cow<'static, str>>) -> Self {
+        self.cookie_name = cookie_name.into();
+        self
+    }
+
+    /// Set the `Path` attribute of the flash cookie.
+    ///
+    /// Useful if your app is mounted under a subpath. Defaults to `"/"`.
+    pub fn cookie_path(mut self, cookie_path: impl Into<Cow<'static, str>>) -> Self {
+        self.cookie_path = cookie_path.into();
+        self
+    }
+
+    /// Set how long the flash cookie lives before expiring.
+    ///
+    /// Defaults to 10 minutes.
+    pub fn cookie_max_age(mut self, cookie_max_age: Duration) -> Self {
+        self.cookie_max_age = cookie_max_age;
+        self
+    }
+
+    /// Set the `SameSite` attribute of the flash cookie.
+    ///
+    /// For example, apps relying on a redirect back from an OAuth provider
+    /// need `SameSite::Lax` since `SameSite::Strict` (the default) won't be
+    /// sent on that cross-site redirect.
+    pub fn same_site(mut self, same_site: cookie::SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Set the maximum size in bytes of the cookie value once the serialized
+    /// flash messages have been signed (or encrypted, see
+    /// [`Config::encrypt_cookies`]) -- i.e. including the signature or
+    /// encryption overhead, not just the raw JSON.
+    ///
+    /// Browsers refuse to store cookies larger than about 4 KB, silently
+    /// dropping the whole cookie (and every message in it) if that happens.
+    /// When the encoded messages would exceed this limit, the oldest ones
+    /// are evicted first so the most recent messages survive.
+    ///
+    /// Defaults to 3900 bytes, leaving headroom for the cookie's other
+    /// attributes (name, path, flags, ...).
+    pub fn max_cookie_size(mut self, max_cookie_size: usize) -> Self {
+        self.max_cookie_size = max_cookie_size;
+        self
+    }
 }
 
 impl fmt::Debug for Config {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Config")
             .field("use_secure_cookies", &self.use_secure_cookies)
+            .field("encrypt_cookies", &self.encrypt_cookies)
             .field("key", &"REDACTED")
+            .field("cookie_name", &self.cookie_name)
+            .field("cookie_path", &self.cookie_path)
+            .field("cookie_max_age", &self.cookie_max_age)
+            .field("same_site", &self.same_site)
+            .field("max_cookie_size", &self.max_cookie_size)
             .finish()
     }
 }
@@ -295,46 +493,80 @@ impl fmt::Debug for Config {
 /// Extractor for incoming flash messages.
 ///
 /// See [root module docs](crate) for an example.
+///
+/// `T` is the type of the payload carried by each flash message and must
+/// match the `T` used to [`push_value`](Flash::push_value) it. Defaults to
+/// `String`.
 #[derive(Clone)]
-pub struct IncomingFlashes {
-    flashes: Vec<FlashMessage>,
+pub struct IncomingFlashes<T = String> {
+    flashes: Vec<FlashMessage<T>>,
     use_secure_cookies: bool,
+    encrypt: bool,
     key: Key,
+    cookie_name: Cow<'static, str>,
+    cookie_path: Cow<'static, str>,
+    cookie_max_age: Duration,
+    same_site: cookie::SameSite,
+    // Tracks whether the flashes have been looked at, via `iter`, `len`, or
+    // `is_empty`. Only once that's happened do we clear the cookie in
+    // `into_response_parts` -- otherwise a handler that merely extracts
+    // `IncomingFlashes` without reading it (e.g. for an unrelated layout)
+    // would destroy messages a later page still wants to show.
+    read: Cell<bool>,
 }
 
-impl fmt::Debug for IncomingFlashes {
+impl<T> fmt::Debug for IncomingFlashes<T>
+where
+    T: fmt::Debug,
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("IncomingFlashes")
             .field("flashes", &self.flashes)
             .field("use_secure_cookies", &self.use_secure_cookies)
+            .field("encrypt", &self.encrypt)
             .field("key", &"REDACTED")
+            .field("cookie_name", &self.cookie_name)
+            .field("cookie_path", &self.cookie_path)
+            .field("cookie_max_age", &self.cookie_max_age)
+            .field("same_site", &self.same_site)
+            .field("read", &self.read)
             .finish()
     }
 }
 
-impl IncomingFlashes {
+impl<T> IncomingFlashes<T> {
     /// Get an iterator over the flash messages.
-    pub fn iter(&self) -> Iter<'_> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.read.set(true);
         Iter(self.flashes.iter())
     }
 
     /// Get the number of flash messages.
     pub fn len(&self) -> usize {
+        self.read.set(true);
         self.flashes.len()
     }
 
     /// Whether there are any flash messages or not.
     pub fn is_empty(&self) -> bool {
+        self.read.set(true);
         self.flashes.is_empty()
     }
 }
 
 /// An iterator over the flash messages.
+///
+/// Yields `(Level, &'a T)`, i.e. `(Level, &'a String)` for the default
+/// `T = String`. This is a deliberate breaking change from the previous,
+/// non-generic version of this crate, which always yielded
+/// `(Level, &'a str)` -- code that matched on `&str` (e.g.
+/// `match text { "foo" => ... }`) needs `match text.as_str()` instead after
+/// upgrading.
 #[derive(Debug)]
-pub struct Iter<'a>(std::slice::Iter<'a, FlashMessage>);
+pub struct Iter<'a, T = String>(std::slice::Iter<'a, FlashMessage<T>>);
 
-impl<'a> Iterator for Iter<'a> {
-    type Item = (Level, &'a str);
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Level, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
         let message = self.0.next()?;
@@ -342,9 +574,9 @@ impl<'a> Iterator for Iter<'a> {
     }
 }
 
-impl<'a> IntoIterator for &'a IncomingFlashes {
-    type Item = (Level, &'a str);
-    type IntoIter = Iter<'a>;
+impl<'a, T> IntoIterator for &'a IncomingFlashes<T> {
+    type Item = (Level, &'a T);
+    type IntoIter = Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
@@ -352,45 +584,102 @@ impl<'a> IntoIterator for &'a IncomingFlashes {
 }
 
 #[async_trait]
-impl<S> FromRequestParts<S> for IncomingFlashes
+impl<S, T> FromRequestParts<S> for IncomingFlashes<T>
 where
     S: Send + Sync,
     Config: FromRef<S>,
+    T: DeserializeOwned,
 {
     type Rejection = (StatusCode, &'static str);
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let config = Config::from_ref(state);
-        let cookies = SignedCookieJar::from_headers(&parts.headers, config.key.clone());
 
-        let flashes = cookies
-            .get(COOKIE_NAME)
-            .map(|cookie| cookie.into_owned())
-            .and_then(|cookie| serde_json::from_str::<Vec<FlashMessage>>(cookie.value()).ok())
+        let cookie = if config.encrypt_cookies {
+            let cookies = PrivateCookieJar::from_headers(&parts.headers, config.key.clone());
+            cookies
+                .get(config.cookie_name.as_ref())
+                .map(|cookie| cookie.into_owned())
+        } else {
+            let cookies = SignedCookieJar::from_headers(&parts.headers, config.key.clone());
+            cookies
+                .get(config.cookie_name.as_ref())
+                .map(|cookie| cookie.into_owned())
+        };
+
+        let flashes = cookie
+            .and_then(|cookie| serde_json::from_str::<Vec<FlashMessage<T>>>(cookie.value()).ok())
             .unwrap_or_default();
 
         Ok(Self {
             flashes,
             use_secure_cookies: config.use_secure_cookies,
+            encrypt: config.encrypt_cookies,
             key: config.key,
+            cookie_name: config.cookie_name,
+            cookie_path: config.cookie_path,
+            cookie_max_age: config.cookie_max_age,
+            same_site: config.same_site,
+            read: Cell::new(false),
         })
     }
 }
 
-impl IntoResponseParts for IncomingFlashes {
+impl<T> IntoResponseParts for IncomingFlashes<T>
+where
+    T: Serialize,
+{
     type Error = Infallible;
 
     fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
-        let cookies = SignedCookieJar::from_headers(res.headers(), self.key);
+        if !self.read.get() && self.flashes.is_empty() {
+            // Nobody looked at the flashes and there weren't any to begin
+            // with, so don't plant an empty flash cookie on every response
+            // from a handler that merely extracts `IncomingFlashes` (e.g.
+            // for an unrelated layout).
+            return Ok(res);
+        }
 
-        let mut cookie = create_cookie("".to_owned(), self.use_secure_cookies);
-        cookie.make_removal();
-        let cookies = cookies.add(cookie);
-        cookies.into_response_parts(res)
+        let cookie = if self.read.get() {
+            let mut cookie = create_cookie(
+                self.cookie_name,
+                "".to_owned(),
+                self.cookie_path,
+                self.cookie_max_age,
+                self.same_site,
+                self.use_secure_cookies,
+            );
+            cookie.make_removal();
+            cookie
+        } else {
+            // Nobody looked at the flashes, so keep them around for the next
+            // request by writing them straight back out.
+            let json = serde_json::to_string(&self.flashes)
+                .expect("failed to serialize flash messages");
+            create_cookie(
+                self.cookie_name,
+                json,
+                self.cookie_path,
+                self.cookie_max_age,
+                self.same_site,
+                self.use_secure_cookies,
+            )
+        };
+
+        if self.encrypt {
+            let cookies = PrivateCookieJar::from_headers(res.headers(), self.key).add(cookie);
+            cookies.into_response_parts(res)
+        } else {
+            let cookies = SignedCookieJar::from_headers(res.headers(), self.key).add(cookie);
+            cookies.into_response_parts(res)
+        }
     }
 }
 
-impl IntoResponse for IncomingFlashes {
+impl<T> IntoResponse for IncomingFlashes<T>
+where
+    T: Send + Serialize,
+{
     fn into_response(self) -> Response {
         (self, ()).into_response()
     }
@@ -457,4 +746,266 @@ mod tests {
         let body = String::from_utf8(bytes.to_vec()).unwrap();
         assert_eq!(body, "Debug: Hi from flash!");
     }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Note {
+        title: String,
+        body: String,
+    }
+
+    #[tokio::test]
+    async fn generic_payload_round_trips() {
+        let config = Config::new(Key::generate()).use_secure_cookies(false);
+
+        let app = Router::new()
+            .route("/", get(root))
+            .route("/set-flash", get(set_flash))
+            .with_state(config);
+
+        async fn root(flashes: IncomingFlashes<Note>) -> (IncomingFlashes<Note>, String) {
+            let note = flashes.iter().next().unwrap().1.clone();
+            (flashes, format!("{}: {}", note.title, note.body))
+        }
+
+        #[axum::debug_handler(state = Config)]
+        async fn set_flash(flash: Flash<Note>) -> (Flash<Note>, Redirect) {
+            let note = Note {
+                title: "hello".to_owned(),
+                body: "world".to_owned(),
+            };
+            (flash.push_value(Level::Info, note), Redirect::to("/"))
+        }
+
+        let request = Request::builder()
+            .uri("/set-flash")
+            .body(Body::empty())
+            .unwrap();
+        let mut response = app.clone().oneshot(request).await.unwrap();
+        let cookie = response.headers_mut().remove(header::SET_COOKIE).unwrap();
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert_eq!(body, "hello: world");
+    }
+
+    #[tokio::test]
+    async fn encrypted_cookies_round_trip_without_leaking_plaintext() {
+        let config = Config::new(Key::generate())
+            .use_secure_cookies(false)
+            .encrypt_cookies(true);
+
+        let app = Router::new()
+            .route("/", get(root))
+            .route("/set-flash", get(set_flash))
+            .with_state(config);
+
+        async fn root(flash: IncomingFlashes) -> (IncomingFlashes, String) {
+            let messages = flash
+                .iter()
+                .map(|(_, text)| text.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            (flash, messages)
+        }
+
+        #[axum::debug_handler(state = Config)]
+        async fn set_flash(flash: Flash) -> (Flash, Redirect) {
+            (flash.info("secret message"), Redirect::to("/"))
+        }
+
+        let request = Request::builder()
+            .uri("/set-flash")
+            .body(Body::empty())
+            .unwrap();
+        let mut response = app.clone().oneshot(request).await.unwrap();
+        let cookie = response.headers_mut().remove(header::SET_COOKIE).unwrap();
+
+        assert!(!cookie
+            .to_str()
+            .unwrap()
+            .to_lowercase()
+            .contains("secret message"));
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert_eq!(body, "secret message");
+    }
+
+    #[tokio::test]
+    async fn unread_flashes_are_kept_around_until_read() {
+        let config = Config::new(Key::generate()).use_secure_cookies(false);
+
+        let app = Router::new()
+            .route("/", get(root))
+            .route("/set-flash", get(set_flash))
+            .with_state(config);
+
+        async fn root(flash: IncomingFlashes) -> IncomingFlashes {
+            // Don't call `.iter()`/`.len()`/`.is_empty()`, so the flashes are
+            // never marked as read.
+            flash
+        }
+
+        #[axum::debug_handler(state = Config)]
+        async fn set_flash(flash: Flash) -> (Flash, Redirect) {
+            (flash.info("still here"), Redirect::to("/"))
+        }
+
+        let request = Request::builder()
+            .uri("/set-flash")
+            .body(Body::empty())
+            .unwrap();
+        let mut response = app.clone().oneshot(request).await.unwrap();
+        let cookie = response.headers_mut().remove(header::SET_COOKIE).unwrap();
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        let mut response = app.clone().oneshot(request).await.unwrap();
+
+        // Not read, so the cookie is written back out with the same
+        // messages rather than being cleared.
+        let cookie = response
+            .headers_mut()
+            .remove(header::SET_COOKIE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert!(!cookie.contains("Max-Age=0"));
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+
+        // Still not read here either, so it's written back out again.
+        assert!(!response.headers()[header::SET_COOKIE]
+            .to_str()
+            .unwrap()
+            .contains("Max-Age=0"));
+    }
+
+    #[tokio::test]
+    async fn custom_cookie_attributes_are_respected() {
+        let config = Config::new(Key::generate())
+            .use_secure_cookies(false)
+            .cookie_name("custom-name")
+            .cookie_path("/custom-path")
+            .cookie_max_age(Duration::from_secs(42))
+            .same_site(cookie::SameSite::Lax);
+
+        let app = Router::new()
+            .route("/", get(root))
+            .route("/set-flash", get(set_flash))
+            .with_state(config);
+
+        async fn root(flash: IncomingFlashes) -> IncomingFlashes {
+            let _ = flash.iter().count();
+            flash
+        }
+
+        #[axum::debug_handler(state = Config)]
+        async fn set_flash(flash: Flash) -> (Flash, Redirect) {
+            (flash.info("hi"), Redirect::to("/"))
+        }
+
+        let request = Request::builder()
+            .uri("/set-flash")
+            .body(Body::empty())
+            .unwrap();
+        let mut response = app.clone().oneshot(request).await.unwrap();
+        let set_cookie = response
+            .headers_mut()
+            .remove(header::SET_COOKIE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        assert!(set_cookie.starts_with("custom-name="));
+        assert!(set_cookie.contains("Path=/custom-path"));
+        assert!(set_cookie.contains("Max-Age=42"));
+        assert!(set_cookie.contains("SameSite=Lax"));
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, set_cookie)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+
+        let removal_cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        assert!(removal_cookie.starts_with("custom-name="));
+        assert!(removal_cookie.contains("Path=/custom-path"));
+        assert!(removal_cookie.contains("Max-Age=0"));
+    }
+
+    #[tokio::test]
+    async fn eviction_keeps_the_newest_messages() {
+        let config = Config::new(Key::generate())
+            .use_secure_cookies(false)
+            .max_cookie_size(100);
+
+        let app = Router::new()
+            .route("/", get(root))
+            .route("/set-flash", get(set_flash))
+            .with_state(config);
+
+        async fn root(flash: IncomingFlashes) -> (IncomingFlashes, String) {
+            let messages = flash
+                .iter()
+                .map(|(_, text)| text.clone())
+                .collect::<Vec<_>>()
+                .join(", ");
+            (flash, messages)
+        }
+
+        #[axum::debug_handler(state = Config)]
+        async fn set_flash(flash: Flash) -> (Flash, Redirect) {
+            let flash = (0..20).fold(flash, |flash, i| flash.info(format!("message {i}")));
+            assert!(flash.would_truncate());
+            (flash, Redirect::to("/"))
+        }
+
+        let request = Request::builder()
+            .uri("/set-flash")
+            .body(Body::empty())
+            .unwrap();
+        let mut response = app.clone().oneshot(request).await.unwrap();
+        let cookie = response.headers_mut().remove(header::SET_COOKIE).unwrap();
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::COOKIE, cookie)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+
+        // The oldest messages should have been evicted, so only the newest
+        // ones (with the highest indices) survive.
+        assert!(body.contains("message 19"));
+        assert!(!body.contains("message 0,"));
+    }
 }